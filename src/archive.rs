@@ -0,0 +1,49 @@
+use anyhow::anyhow;
+use std::path::Path;
+
+/// Extracts a `.zip` archive's files (flattening directory structure) into
+/// `dest_dir`. Used for chromedriver, msedgedriver and Windows geckodriver
+/// downloads, which are all distributed as zip archives.
+pub fn unzip(body: Vec<u8>, dest_dir: &Path) -> anyhow::Result<()> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(body))?;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let outpath = file.mangled_name();
+        if file.name().ends_with('/') {
+            std::fs::create_dir_all(dest_dir.join(&outpath))?;
+        } else {
+            let outpath_relative = outpath.file_name().ok_or_else(|| {
+                anyhow!(
+                    "couldn't get file name from path: {}",
+                    outpath.to_string_lossy()
+                )
+            })?;
+            let mut outfile = std::fs::File::create(dest_dir.join(outpath_relative))?;
+            std::io::copy(&mut file, &mut outfile)?;
+        }
+    }
+    Ok(())
+}
+
+/// Extracts a `.tar.gz` archive's files (flattening directory structure)
+/// into `dest_dir`. Used for the Linux/macOS geckodriver downloads.
+pub fn untar_gz(body: Vec<u8>, dest_dir: &Path) -> anyhow::Result<()> {
+    let decoder = flate2::read::GzDecoder::new(std::io::Cursor::new(body));
+    let mut archive = tar::Archive::new(decoder);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        if entry.header().entry_type().is_dir() {
+            continue;
+        }
+        let outpath_relative = path.file_name().ok_or_else(|| {
+            anyhow!(
+                "couldn't get file name from path: {}",
+                path.to_string_lossy()
+            )
+        })?;
+        let mut outfile = std::fs::File::create(dest_dir.join(outpath_relative))?;
+        std::io::copy(&mut entry, &mut outfile)?;
+    }
+    Ok(())
+}
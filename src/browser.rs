@@ -0,0 +1,141 @@
+use std::path::PathBuf;
+
+use crate::OS;
+
+/// Chrome release channel to discover and launch.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Channel {
+    #[default]
+    Stable,
+    Beta,
+    Dev,
+    Chromium,
+}
+
+/// Resolves the path to the Chrome/Chromium binary for `channel`, probing
+/// standard install locations (and falling back through the other channels
+/// for non-default installs) when no explicit path was supplied.
+pub async fn discover_browser_path(os: OS, channel: Channel) -> anyhow::Result<PathBuf> {
+    match os {
+        OS::Linux => discover_linux(channel),
+        OS::MacOS => discover_macos(channel),
+        OS::Windows => discover_windows(channel),
+    }
+}
+
+fn discover_linux(channel: Channel) -> anyhow::Result<PathBuf> {
+    let candidates: &[&str] = match channel {
+        Channel::Stable => &["google-chrome", "google-chrome-stable"],
+        Channel::Beta => &["google-chrome-beta"],
+        Channel::Dev => &["google-chrome-unstable", "google-chrome-dev"],
+        Channel::Chromium => &["chromium", "chromium-browser"],
+    };
+    let fallback: &[&str] = &[
+        "google-chrome",
+        "google-chrome-stable",
+        "google-chrome-beta",
+        "google-chrome-unstable",
+        "chromium",
+        "chromium-browser",
+    ];
+    find_in_path(candidates)
+        .or_else(|| find_in_path(fallback))
+        .ok_or_else(|| anyhow::anyhow!("could not find a Chrome/Chromium binary on PATH"))
+}
+
+/// Scans `PATH` for the first of `bin_names` that exists as a file,
+/// mimicking a `which`-style lookup.
+pub(crate) fn find_in_path(bin_names: &[&str]) -> Option<PathBuf> {
+    let path_var = std::env::var_os("PATH")?;
+    for dir in std::env::split_paths(&path_var) {
+        for name in bin_names {
+            let candidate = dir.join(name);
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
+fn discover_macos(channel: Channel) -> anyhow::Result<PathBuf> {
+    let candidates: &[&str] = match channel {
+        Channel::Stable => &["/Applications/Google Chrome.app/Contents/MacOS/Google Chrome"],
+        Channel::Beta => &["/Applications/Google Chrome Beta.app/Contents/MacOS/Google Chrome Beta"],
+        Channel::Dev => &["/Applications/Google Chrome Dev.app/Contents/MacOS/Google Chrome Dev"],
+        Channel::Chromium => &["/Applications/Chromium.app/Contents/MacOS/Chromium"],
+    };
+    let fallback: &[&str] = &[
+        "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+        "/Applications/Google Chrome Beta.app/Contents/MacOS/Google Chrome Beta",
+        "/Applications/Google Chrome Dev.app/Contents/MacOS/Google Chrome Dev",
+        "/Applications/Chromium.app/Contents/MacOS/Chromium",
+    ];
+    candidates
+        .iter()
+        .chain(fallback)
+        .map(PathBuf::from)
+        .find(|path| path.is_file())
+        .ok_or_else(|| anyhow::anyhow!("could not find a Chrome/Chromium bundle in /Applications"))
+}
+
+#[cfg(windows)]
+fn discover_windows(channel: Channel) -> anyhow::Result<PathBuf> {
+    // The App Paths registry key is only ever registered for the Stable
+    // install; Beta/Dev/Chromium are only discoverable via their install path.
+    if channel == Channel::Stable {
+        if let Some(path) = read_app_paths_registry() {
+            return Ok(path);
+        }
+    }
+
+    let candidates: &[&str] = match channel {
+        Channel::Stable => &["Google/Chrome/Application/chrome.exe"],
+        Channel::Beta => &["Google/Chrome Beta/Application/chrome.exe"],
+        Channel::Dev => &["Google/Chrome Dev/Application/chrome.exe"],
+        Channel::Chromium => &["Chromium/Application/chrome.exe"],
+    };
+    let fallback: &[&str] = &[
+        "Google/Chrome/Application/chrome.exe",
+        "Google/Chrome Beta/Application/chrome.exe",
+        "Google/Chrome Dev/Application/chrome.exe",
+        "Chromium/Application/chrome.exe",
+    ];
+
+    let bases: Vec<PathBuf> = ["ProgramFiles(x86)", "ProgramFiles", "LOCALAPPDATA"]
+        .iter()
+        .filter_map(|var| std::env::var_os(var))
+        .map(PathBuf::from)
+        .collect();
+
+    candidates
+        .iter()
+        .chain(fallback)
+        .flat_map(|relative| bases.iter().map(move |base| base.join(relative)))
+        .find(|path| path.is_file())
+        .ok_or_else(|| anyhow::anyhow!("could not find chrome.exe in the registry or standard install locations"))
+}
+
+#[cfg(windows)]
+fn read_app_paths_registry() -> Option<PathBuf> {
+    use winreg::enums::HKEY_LOCAL_MACHINE;
+    use winreg::RegKey;
+
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    for subkey in [
+        r"SOFTWARE\Microsoft\Windows\CurrentVersion\App Paths\chrome.exe",
+        r"SOFTWARE\WOW6432Node\Microsoft\Windows\CurrentVersion\App Paths\chrome.exe",
+    ] {
+        if let Ok(key) = hklm.open_subkey(subkey) {
+            if let Ok(path) = key.get_value::<String, _>("") {
+                return Some(PathBuf::from(path));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(not(windows))]
+fn discover_windows(_channel: Channel) -> anyhow::Result<PathBuf> {
+    anyhow::bail!("Windows browser discovery is unavailable on this target")
+}
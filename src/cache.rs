@@ -0,0 +1,105 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+const CACHE_DIR_NAME: &str = "undetected-chromedriver";
+
+/// Metadata describing the driver binary cached under a version directory
+/// (e.g. a Chrome major version, or a browser name for drivers that aren't
+/// pinned to an installed browser version), written alongside the binary so
+/// subsequent runs can decide whether a re-download is necessary.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CacheMetadata {
+    pub version_key: String,
+    pub driver_version: String,
+    pub driver_path: PathBuf,
+    pub timestamp: u64,
+}
+
+/// Resolves the root cache directory (e.g. `~/.cache/undetected-chromedriver`
+/// on Linux, or the OS equivalent). The directory itself is not created here;
+/// [`version_dir`] creates it along with the per-version subdirectory.
+pub fn cache_root() -> anyhow::Result<PathBuf> {
+    let base_dirs = directories::BaseDirs::new()
+        .ok_or_else(|| anyhow::anyhow!("could not resolve home directory"))?;
+    Ok(base_dirs.cache_dir().join(CACHE_DIR_NAME))
+}
+
+/// Resolves (and creates) the cache directory for a version key, e.g.
+/// `~/.cache/undetected-chromedriver/chrome-122`.
+pub async fn version_dir(version_key: &str) -> anyhow::Result<PathBuf> {
+    let dir = cache_root()?.join(version_key);
+    tokio::fs::create_dir_all(&dir).await?;
+    Ok(dir)
+}
+
+fn metadata_path(version_dir: &Path) -> PathBuf {
+    version_dir.join("metadata.json")
+}
+
+/// Reads the cache metadata for a version directory, if present.
+pub async fn read_metadata(version_dir: &Path) -> Option<CacheMetadata> {
+    let bytes = tokio::fs::read(metadata_path(version_dir)).await.ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// Writes the cache metadata for a version directory.
+pub async fn write_metadata(
+    version_dir: &Path,
+    version_key: String,
+    driver_version: String,
+    driver_path: PathBuf,
+) -> anyhow::Result<()> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let metadata = CacheMetadata {
+        version_key,
+        driver_version,
+        driver_path,
+        timestamp,
+    };
+    let bytes = serde_json::to_vec_pretty(&metadata)?;
+    tokio::fs::write(metadata_path(version_dir), bytes).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "undetected-chromedriver-test-{}-{}",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[tokio::test]
+    async fn round_trips_metadata_through_a_version_dir() {
+        let dir = scratch_dir("roundtrip");
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        write_metadata(
+            &dir,
+            "122".to_string(),
+            "122.0.6261.111".to_string(),
+            dir.join("chromedriver"),
+        )
+        .await
+        .unwrap();
+
+        let metadata = read_metadata(&dir).await.unwrap();
+        assert_eq!(metadata.version_key, "122");
+        assert_eq!(metadata.driver_version, "122.0.6261.111");
+        assert_eq!(metadata.driver_path, dir.join("chromedriver"));
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn missing_metadata_file_reads_as_none() {
+        let dir = scratch_dir("missing");
+        assert!(read_metadata(&dir).await.is_none());
+    }
+}
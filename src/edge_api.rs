@@ -0,0 +1,39 @@
+use std::path::Path;
+
+use crate::archive::unzip;
+use crate::{Arch, OS};
+
+/// Downloads the latest stable msedgedriver release into `dest_dir`,
+/// returning the resolved version string.
+pub async fn fetch_edgedriver(
+    client: &reqwest::Client,
+    os: OS,
+    arch: Arch,
+    dest_dir: &Path,
+) -> anyhow::Result<String> {
+    let version = client
+        .get("https://msedgedriver.azureedge.net/LATEST_STABLE")
+        .send()
+        .await?
+        .text()
+        .await?
+        .trim()
+        .trim_start_matches('\u{feff}')
+        .to_string();
+
+    let platform = match (os, arch) {
+        (OS::Linux, _) => "linux64",
+        (OS::MacOS, Arch::Arm64) => "mac64_m1",
+        (OS::MacOS, _) => "mac64",
+        (OS::Windows, Arch::X86) => "win32",
+        (OS::Windows, _) => "win64",
+    };
+    let edgedriver_url =
+        format!("https://msedgedriver.azureedge.net/{version}/edgedriver_{platform}.zip");
+
+    let resp = client.get(&edgedriver_url).send().await?;
+    resp.error_for_status_ref()?;
+    let body = resp.bytes().await?;
+    unzip(body.to_vec(), dest_dir)?;
+    Ok(version)
+}
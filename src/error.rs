@@ -0,0 +1,14 @@
+use thiserror::Error;
+
+/// Errors that can occur while locating a driver binary on `PATH`, allocating
+/// a port for it, and waiting on the readiness of a freshly spawned driver
+/// process (chromedriver, geckodriver or msedgedriver).
+#[derive(Error, Debug)]
+pub enum DriverError {
+    #[error("could not find an available port for the driver")]
+    NoAvailablePorts,
+    #[error("timed out waiting for the driver to report it was started")]
+    PortOpenTimeout,
+    #[error("use_path_driver() was set but no chromedriver binary was found on PATH")]
+    DriverNotOnPath,
+}
@@ -0,0 +1,52 @@
+use serde::Deserialize;
+use std::path::Path;
+
+use crate::archive::{untar_gz, unzip};
+use crate::{Arch, OS};
+
+#[derive(Deserialize, Debug)]
+struct Release {
+    tag_name: String,
+}
+
+/// Downloads the latest geckodriver release from GitHub into `dest_dir`,
+/// returning the resolved version tag.
+pub async fn fetch_geckodriver(
+    client: &reqwest::Client,
+    os: OS,
+    arch: Arch,
+    dest_dir: &Path,
+) -> anyhow::Result<String> {
+    let release: Release = client
+        .get("https://api.github.com/repos/mozilla/geckodriver/releases/latest")
+        .header("User-Agent", "undetected-chromedriver")
+        .send()
+        .await?
+        .json()
+        .await?;
+    let version = release.tag_name;
+
+    let platform = match (os, arch) {
+        (OS::Linux, Arch::Arm64) => "linux-aarch64",
+        (OS::Linux, _) => "linux64",
+        (OS::MacOS, Arch::Arm64) => "macos-aarch64",
+        (OS::MacOS, _) => "macos",
+        (OS::Windows, Arch::X86) => "win32",
+        (OS::Windows, _) => "win64",
+    };
+    let is_zip = os == OS::Windows;
+    let ext = if is_zip { "zip" } else { "tar.gz" };
+    let geckodriver_url = format!(
+        "https://github.com/mozilla/geckodriver/releases/download/{version}/geckodriver-{version}-{platform}.{ext}",
+    );
+
+    let resp = client.get(&geckodriver_url).send().await?;
+    resp.error_for_status_ref()?;
+    let body = resp.bytes().await?;
+    if is_zip {
+        unzip(body.to_vec(), dest_dir)?;
+    } else {
+        untar_gz(body.to_vec(), dest_dir)?;
+    }
+    Ok(version)
+}
@@ -1,9 +1,11 @@
 use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 use tokio::process::Command;
 
-use crate::OS;
+use crate::archive::unzip;
+use crate::{Arch, OS};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct MilestoneVersions {
@@ -18,24 +20,16 @@ pub struct Milestone {
     pub revision: String,
 }
 
-pub async fn get_chrome_version(os: OS) -> anyhow::Result<String> {
+pub async fn get_chrome_version(os: OS, browser_path: &Path) -> anyhow::Result<String> {
     log::info!("Getting installed Chrome version...");
     let command = match os {
-        OS::Linux => {
-            Command::new("google-chrome-stable")
-                .arg("--version")
-                .output()
-                .await?
-        }
-        OS::MacOS => {
-            Command::new("/Applications/Google Chrome.app/Contents/MacOS/Google Chrome")
-                .arg("--version")
-                .output()
-                .await?
-        }
+        OS::Linux | OS::MacOS => Command::new(browser_path).arg("--version").output().await?,
         OS::Windows => Command::new("powershell")
             .arg("-c")
-            .arg("(Get-Item 'C:/Program Files/Google/Chrome/Application/chrome.exe').VersionInfo")
+            .arg(format!(
+                "(Get-Item '{}').VersionInfo",
+                browser_path.display()
+            ))
             .output()
             .await?,
     };
@@ -51,17 +45,26 @@ pub async fn get_chrome_version(os: OS) -> anyhow::Result<String> {
     Ok(version)
 }
 
-pub async fn fetch_chromedriver(client: &reqwest::Client, os: OS) -> anyhow::Result<()> {
-    let installed_version = get_chrome_version(os).await?;
+/// Downloads the chromedriver build matching `installed_version` (as
+/// resolved by [`get_chrome_version`]) into `dest_dir`, returning the
+/// resolved driver version string.
+pub async fn fetch_chromedriver(
+    client: &reqwest::Client,
+    os: OS,
+    arch: Arch,
+    installed_version: &str,
+    dest_dir: &Path,
+) -> anyhow::Result<String> {
     let chromedriver_url: String;
-    if installed_version.as_str() >= "114" {
+    let driver_version: String;
+    if installed_version >= "114" {
         // Fetch the correct version
         let url = "https://googlechromelabs.github.io/chrome-for-testing/latest-versions-per-milestone.json";
         let resp = client.get(url).send().await?;
         let milestone_versions: MilestoneVersions = resp.json().await?;
         let version = milestone_versions
             .milestones
-            .get(&installed_version)
+            .get(installed_version)
             .ok_or_else(|| {
                 anyhow!(
                     "Could not find version {} in the latest-versions-per-milestone.json file",
@@ -70,22 +73,20 @@ pub async fn fetch_chromedriver(client: &reqwest::Client, os: OS) -> anyhow::Res
             })?
             .version
             .as_str();
+        driver_version = version.to_string();
 
         // Fetch the chromedriver binary
-        chromedriver_url = match os {
-            OS::Linux => format!(
-                "https://storage.googleapis.com/chrome-for-testing-public/{}/linux64/chromedriver-linux64.zip",
-                version
-            ),
-            OS::MacOS => format!(
-                "https://storage.googleapis.com/chrome-for-testing-public/{}/mac-arm64/chromedriver-mac-arm64.zip",
-                version
-            ),
-            OS::Windows => format!(
-                "https://storage.googleapis.com/chrome-for-testing-public/{}/win64/chrome-win64.zip",
-                version,
-            ),
+        let platform = match (os, arch) {
+            (OS::Linux, _) => "linux64",
+            (OS::MacOS, Arch::Arm64) => "mac-arm64",
+            (OS::MacOS, _) => "mac-x64",
+            (OS::Windows, Arch::X86) => "win32",
+            (OS::Windows, _) => "win64",
         };
+        chromedriver_url = format!(
+            "https://storage.googleapis.com/chrome-for-testing-public/{}/{}/chromedriver-{}.zip",
+            version, platform, platform
+        );
     } else {
         let resp = client
             .get(format!(
@@ -95,6 +96,7 @@ pub async fn fetch_chromedriver(client: &reqwest::Client, os: OS) -> anyhow::Res
             .send()
             .await?;
         let body = resp.text().await?;
+        driver_version = body.clone();
         chromedriver_url = match os {
             OS::Linux => format!(
                 "https://chromedriver.storage.googleapis.com/{}/chromedriver_linux64.zip",
@@ -114,27 +116,6 @@ pub async fn fetch_chromedriver(client: &reqwest::Client, os: OS) -> anyhow::Res
     let resp = client.get(&chromedriver_url).send().await?;
     resp.error_for_status_ref()?;
     let body = resp.bytes().await?;
-    unzip_chromedriver(body.to_vec())?;
-    Ok(())
-}
-
-fn unzip_chromedriver(body: Vec<u8>) -> anyhow::Result<()> {
-    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(body))?;
-    for i in 0..archive.len() {
-        let mut file = archive.by_index(i)?;
-        let outpath = file.mangled_name();
-        if file.name().ends_with('/') {
-            std::fs::create_dir_all(&outpath)?;
-        } else {
-            let outpath_relative = outpath.file_name().ok_or_else(|| {
-                anyhow!(
-                    "couldn't get file name from path: {}",
-                    outpath.to_string_lossy()
-                )
-            })?;
-            let mut outfile = std::fs::File::create(outpath_relative)?;
-            std::io::copy(&mut file, &mut outfile)?;
-        }
-    }
-    Ok(())
+    unzip(body.to_vec(), dest_dir)?;
+    Ok(driver_version)
 }
@@ -1,16 +1,32 @@
+mod archive;
+mod browser;
+mod cache;
+mod edge_api;
+mod error;
+mod gecko_api;
 mod google_api;
 
+pub use browser::Channel;
+
 #[cfg(any(target_os = "linux", target_os = "macos"))]
 use std::os::unix::fs::PermissionsExt;
 use std::{
+    net::TcpListener,
     ops::{Deref, DerefMut},
+    path::{Path, PathBuf},
+    process::Stdio,
     sync::Arc,
+    time::Duration,
 };
 
 use rand::Rng;
 use thirtyfour::{ChromeCapabilities, DesiredCapabilities, WebDriver};
-use tokio::process::{Child, Command};
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    process::{Child, Command},
+};
 
+use crate::error::DriverError;
 use crate::google_api::fetch_chromedriver;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -20,38 +36,112 @@ pub enum OS {
     Windows,
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Arch {
+    X64,
+    X86,
+    Arm64,
+}
+
+impl Arch {
+    fn detect() -> anyhow::Result<Self> {
+        match std::env::consts::ARCH {
+            "x86_64" => Ok(Arch::X64),
+            "x86" => Ok(Arch::X86),
+            "aarch64" => Ok(Arch::Arm64),
+            unknown_arch => anyhow::bail!("unsupported architecture: `{}`", unknown_arch),
+        }
+    }
+}
+
+fn detect_os() -> anyhow::Result<OS> {
+    match std::env::consts::OS {
+        "linux" => Ok(OS::Linux),
+        "macos" => Ok(OS::MacOS),
+        "windows" => Ok(OS::Windows),
+        unknown_os => anyhow::bail!("unsupported OS: `{}`", unknown_os),
+    }
+}
+
+/// The browser (and matching WebDriver implementation) to launch.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Browser {
+    Chrome,
+    Firefox,
+    Edge,
+}
+
+impl Browser {
+    fn cache_name(self) -> &'static str {
+        match self {
+            Browser::Chrome => "chrome",
+            Browser::Firefox => "firefox",
+            Browser::Edge => "edge",
+        }
+    }
+
+    fn driver_file_name(self, os: OS) -> &'static str {
+        match (self, os) {
+            (Browser::Chrome, OS::Windows) => "chromedriver.exe",
+            (Browser::Chrome, _) => "chromedriver",
+            (Browser::Firefox, OS::Windows) => "geckodriver.exe",
+            (Browser::Firefox, _) => "geckodriver",
+            (Browser::Edge, OS::Windows) => "msedgedriver.exe",
+            (Browser::Edge, _) => "msedgedriver",
+        }
+    }
+
+    /// Only chromedriver ships the `cdc_` automation markers this crate
+    /// patches out; geckodriver and msedgedriver don't need it.
+    fn needs_cdc_patch(self) -> bool {
+        matches!(self, Browser::Chrome)
+    }
+
+    fn ready_message(self, port: u16) -> String {
+        match self {
+            Browser::Chrome => format!("ChromeDriver was started successfully on port {}", port),
+            Browser::Edge => format!(
+                "Microsoft Edge WebDriver was started successfully on port {}",
+                port
+            ),
+            Browser::Firefox => format!("Listening on 127.0.0.1:{}", port),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Driver {
     pub url: String,
     pub process: Child,
 }
 
-/// A wrapper around a WebDriver that holds an `Arc<Driver>`
-/// When all `Arc<Driver>` are dropped, the chromedriver process is killed, this
-/// ensures we're not leaking chromedriver processes and occupying ports.
-pub struct UndetectedChrome {
+/// A wrapper around a WebDriver session that holds an `Arc<Driver>`.
+/// When all `Arc<Driver>` are dropped, the underlying driver process (chromedriver,
+/// geckodriver, or msedgedriver) is killed, this ensures we're not leaking
+/// processes and occupying ports.
+pub struct UndetectedDriver {
     pub driver: Arc<Driver>,
-    pub chrome: WebDriver,
+    pub webdriver: WebDriver,
 }
 
-impl UndetectedChrome {
+impl UndetectedDriver {
     pub async fn quit(self) -> anyhow::Result<()> {
-        self.chrome.quit().await?;
+        self.webdriver.quit().await?;
         Ok(())
     }
 }
 
-impl DerefMut for UndetectedChrome {
+impl DerefMut for UndetectedDriver {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.chrome
+        &mut self.webdriver
     }
 }
 
-impl Deref for UndetectedChrome {
+impl Deref for UndetectedDriver {
     type Target = WebDriver;
 
     fn deref(&self) -> &Self::Target {
-        &self.chrome
+        &self.webdriver
     }
 }
 
@@ -59,6 +149,10 @@ impl Deref for UndetectedChrome {
 pub struct ChromeBuilder {
     driver: Option<Arc<Driver>>,
     caps: Option<ChromeCapabilities>,
+    browser_path: Option<PathBuf>,
+    channel: Channel,
+    chromedriver_path: Option<PathBuf>,
+    use_path_driver: bool,
 }
 
 impl ChromeBuilder {
@@ -66,6 +160,10 @@ impl ChromeBuilder {
         Self {
             driver: None,
             caps: None,
+            browser_path: None,
+            channel: Channel::default(),
+            chromedriver_path: None,
+            use_path_driver: false,
         }
     }
 
@@ -79,13 +177,67 @@ impl ChromeBuilder {
         self
     }
 
-    pub async fn build(self) -> anyhow::Result<UndetectedChrome> {
+    /// Points the builder at a specific Chrome/Chromium binary instead of
+    /// letting it discover one from the standard install locations.
+    pub fn with_browser_path(mut self, path: PathBuf) -> Self {
+        self.browser_path = Some(path);
+        self
+    }
+
+    /// Selects which release channel to discover when `with_browser_path`
+    /// isn't used.
+    pub fn with_channel(mut self, channel: Channel) -> Self {
+        self.channel = channel;
+        self
+    }
+
+    /// Uses a pre-installed chromedriver at `path` instead of downloading
+    /// one. The binary is still run through the cdc-patching pass, with the
+    /// patched copy written into the cache dir; `fetch_chromedriver` is
+    /// never called.
+    pub fn with_chromedriver_path(mut self, path: PathBuf) -> Self {
+        self.chromedriver_path = Some(path);
+        self
+    }
+
+    /// Looks for a chromedriver binary on `PATH` instead of downloading one,
+    /// failing with [`DriverError::DriverNotOnPath`] if none is found rather
+    /// than falling back to a network fetch. Ignored if `with_chromedriver_path`
+    /// was also called.
+    pub fn use_path_driver(mut self) -> Self {
+        self.use_path_driver = true;
+        self
+    }
+
+    pub async fn build(self) -> anyhow::Result<UndetectedDriver> {
+        let os = detect_os()?;
+        let browser_path = match self.browser_path {
+            Some(path) => path,
+            None => browser::discover_browser_path(os, self.channel).await?,
+        };
+        let chromedriver_path = match self.chromedriver_path {
+            Some(path) => Some(path),
+            None if self.use_path_driver => {
+                Some(find_chromedriver_on_path(os).ok_or(DriverError::DriverNotOnPath)?)
+            }
+            None => None,
+        };
+
         let mut caps = self.caps.unwrap_or_else(|| DesiredCapabilities::chrome());
         let driver = match self.driver {
             Some(d) => d,
-            None => Arc::new(start_driver().await?),
+            None => Arc::new(
+                start_driver_with(
+                    os,
+                    Browser::Chrome,
+                    Some(browser_path.clone()),
+                    chromedriver_path,
+                )
+                .await?,
+            ),
         };
 
+        caps.set_binary(&browser_path.to_string_lossy()).unwrap();
         caps.set_no_sandbox().unwrap();
         caps.set_disable_dev_shm_usage().unwrap();
         caps.add_chrome_arg("--disable-blink-features=AutomationControlled")
@@ -95,25 +247,8 @@ impl ChromeBuilder {
         caps.add_chrome_arg("disable-infobars").unwrap();
         caps.add_chrome_option("excludeSwitches", ["enable-automation"])
             .unwrap();
-        let mut attempts = 0;
-        let client = reqwest::Client::new();
-        loop {
-            attempts += 1;
-            if client
-                .get(&format!("{}/status", driver.url))
-                .send()
-                .await
-                .is_ok()
-            {
-                break;
-            }
-            if attempts > 20 {
-                anyhow::bail!("failed to connect to chromedriver");
-            }
-            tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
-        }
-        let chrome = WebDriver::new(&driver.url, caps.clone()).await?;
-        Ok(UndetectedChrome { driver, chrome })
+        let webdriver = WebDriver::new(&driver.url, caps.clone()).await?;
+        Ok(UndetectedDriver { driver, webdriver })
     }
 }
 
@@ -122,109 +257,391 @@ fn random_char() -> u8 {
     alphabet[rand::thread_rng().gen_range(0..48)]
 }
 
+/// Looks for a chromedriver binary on `PATH`, for use with
+/// [`ChromeBuilder::use_path_driver`].
+fn find_chromedriver_on_path(os: OS) -> Option<PathBuf> {
+    let name = Browser::Chrome.driver_file_name(os);
+    browser::find_in_path(&[name])
+}
+
 /// Launches a new Chromedriver instance and returns a WebDriver running on it.
-pub async fn chrome() -> anyhow::Result<UndetectedChrome> {
+pub async fn chrome() -> anyhow::Result<UndetectedDriver> {
     ChromeBuilder::new().build().await
 }
 
+/// Launches a Firefox session via geckodriver.
+pub async fn firefox() -> anyhow::Result<UndetectedDriver> {
+    let os = detect_os()?;
+    let driver = Arc::new(start_driver_with(os, Browser::Firefox, None, None).await?);
+    let webdriver = WebDriver::new(&driver.url, DesiredCapabilities::firefox()).await?;
+    Ok(UndetectedDriver { driver, webdriver })
+}
+
+/// Launches an Edge session via msedgedriver.
+pub async fn edge() -> anyhow::Result<UndetectedDriver> {
+    let os = detect_os()?;
+    let driver = Arc::new(start_driver_with(os, Browser::Edge, None, None).await?);
+    let webdriver = WebDriver::new(&driver.url, DesiredCapabilities::edge()).await?;
+    Ok(UndetectedDriver { driver, webdriver })
+}
+
+/// Launches a chromedriver process for the default, auto-discovered Chrome
+/// install. Use [`ChromeBuilder::with_browser_path`]/[`ChromeBuilder::with_channel`]
+/// to target a different binary.
 pub async fn start_driver() -> anyhow::Result<Driver> {
-    let os = match std::env::consts::OS {
-        "linux" => OS::Linux,
-        "macos" => OS::MacOS,
-        "windows" => OS::Windows,
-        unknown_os => anyhow::bail!("unsupported OS: `{}`", unknown_os),
-    };
+    let os = detect_os()?;
+    let browser_path = browser::discover_browser_path(os, Channel::default()).await?;
+    start_driver_with(os, Browser::Chrome, Some(browser_path), None).await
+}
+
+async fn start_driver_with(
+    os: OS,
+    browser: Browser,
+    browser_path: Option<PathBuf>,
+    existing_driver_path: Option<PathBuf>,
+) -> anyhow::Result<Driver> {
+    let arch = Arch::detect()?;
 
-    let chromedriver_exists = match os {
-        OS::Linux | OS::MacOS => tokio::fs::try_exists("chromedriver").await?,
-        OS::Windows => tokio::fs::try_exists("chromedriver.exe").await?,
+    // Chrome's driver is pinned to the installed browser's major version;
+    // geckodriver/msedgedriver are fetched once and reused across runs.
+    let version_key = match browser {
+        Browser::Chrome => {
+            let browser_path =
+                browser_path.expect("Chrome launches always resolve a browser path");
+            google_api::get_chrome_version(os, &browser_path).await?
+        }
+        Browser::Firefox | Browser::Edge => "latest".to_string(),
     };
+    let cache_key = format!("{}-{}", browser.cache_name(), version_key);
+    let version_dir = cache::version_dir(&cache_key).await?;
 
-    if chromedriver_exists {
-        log::info!("ChromeDriver already exists!");
+    let driver_file_name = browser.driver_file_name(os);
+    let final_driver_path = if browser.needs_cdc_patch() {
+        version_dir.join(match os {
+            OS::Linux | OS::MacOS => "chromedriver_PATCHED",
+            OS::Windows => "chromedriver_PATCHED.exe",
+        })
     } else {
-        log::info!("ChromeDriver does not exist! Fetching...");
-        let client = reqwest::Client::new();
-        fetch_chromedriver(&client, os).await?;
-    }
-
-    let patched_chromedriver_path = match os {
-        OS::Linux | OS::MacOS => "chromedriver_PATCHED",
-        OS::Windows => "chromedriver_PATCHED.exe",
+        version_dir.join(driver_file_name)
     };
 
-    if !tokio::fs::try_exists(patched_chromedriver_path).await? {
-        log::info!("patching chromedriver executable");
-        let file_name = if cfg!(windows) {
-            "chromedriver.exe"
-        } else {
-            "chromedriver"
-        };
-        let f = tokio::fs::read(file_name).await?;
-        let mut new_chromedriver_bytes = f.clone();
-        let mut total_cdc = String::from("");
-        let mut cdc_pos_list = Vec::new();
-        let mut is_cdc_present = false;
-        let mut patch_ct = -1;
-        for i in 0..f.len() - 3 {
-            if "cdc_"
-                == format!(
-                    "{}{}{}{}",
-                    f[i] as char,
-                    f[i + 1] as char,
-                    f[i + 2] as char,
-                    f[i + 3] as char
-                )
-                .as_str()
-            {
-                for x in i + 4..i + 22 {
-                    total_cdc.push_str(&(f[x] as char).to_string());
-                }
-                is_cdc_present = true;
-                cdc_pos_list.push(i);
-                total_cdc = String::from("");
-            }
-        }
-        if is_cdc_present {
-            log::info!("Found cdcs!")
-        } else {
-            log::info!("No cdcs were found!")
-        }
+    let cached_metadata = cache::read_metadata(&version_dir).await;
+    let cache_is_fresh = should_use_cached_driver(
+        existing_driver_path.is_some(),
+        cached_metadata.as_ref().map(|m| m.version_key.as_str()),
+        &version_key,
+        tokio::fs::try_exists(&final_driver_path).await?,
+    );
 
-        for i in cdc_pos_list {
-            for x in i + 4..i + 22 {
-                new_chromedriver_bytes[x] = random_char();
-            }
-            patch_ct += 1;
-        }
-        log::info!("Patched {} cdcs!", patch_ct);
+    if let Some(existing_driver_path) = existing_driver_path {
         log::info!(
-            "Writing patched executable to {}...",
-            patched_chromedriver_path
+            "Using provided {:?} driver binary at {}",
+            browser,
+            existing_driver_path.display()
         );
-        tokio::fs::write(patched_chromedriver_path, new_chromedriver_bytes).await?;
+        if browser.needs_cdc_patch() {
+            patch_chromedriver(&existing_driver_path, &final_driver_path).await?;
+        } else {
+            tokio::fs::copy(&existing_driver_path, &final_driver_path).await?;
+        }
+        cache::write_metadata(
+            &version_dir,
+            version_key,
+            "local".to_string(),
+            final_driver_path.clone(),
+        )
+        .await?;
+    } else if cache_is_fresh {
+        log::info!("Using cached {:?} driver ({})", browser, version_key);
+    } else {
         log::info!(
-            "Successfully wrote patched executable to {}",
-            patched_chromedriver_path
+            "No cached {:?} driver ({})! Fetching...",
+            browser,
+            version_key
         );
-    } else {
-        log::info!("Detected patched chromedriver executable!");
+        let client = reqwest::Client::new();
+        let driver_version = match browser {
+            Browser::Chrome => {
+                fetch_chromedriver(&client, os, arch, &version_key, &version_dir).await?
+            }
+            Browser::Firefox => {
+                gecko_api::fetch_geckodriver(&client, os, arch, &version_dir).await?
+            }
+            Browser::Edge => edge_api::fetch_edgedriver(&client, os, arch, &version_dir).await?,
+        };
+
+        if browser.needs_cdc_patch() {
+            patch_chromedriver(&version_dir.join(driver_file_name), &final_driver_path).await?;
+        }
+
+        cache::write_metadata(
+            &version_dir,
+            version_key,
+            driver_version,
+            final_driver_path.clone(),
+        )
+        .await?;
     }
     #[cfg(any(target_os = "linux", target_os = "macos"))]
     {
-        let mut perms = tokio::fs::metadata(patched_chromedriver_path)
+        let mut perms = tokio::fs::metadata(&final_driver_path)
             .await?
             .permissions();
         perms.set_mode(0o755);
-        tokio::fs::set_permissions(patched_chromedriver_path, perms).await?;
+        tokio::fs::set_permissions(&final_driver_path, perms).await?;
     }
 
-    log::info!("Starting chromedriver...");
-    let port: usize = rand::thread_rng().gen_range(2000..5000);
+    log::info!("Starting {:?} driver...", browser);
+    let port = find_available_port()?;
     let url = format!("http://localhost:{}", port);
-    let process = Command::new(format!("./{}", patched_chromedriver_path))
+    // geckodriver writes its "Listening on ..." banner to stderr; chromedriver
+    // and msedgedriver write theirs to stdout. Pipe both and race them rather
+    // than assuming which stream a given driver uses.
+    let mut process = Command::new(&final_driver_path)
         .arg(format!("--port={}", port))
         .kill_on_drop(true)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
         .spawn()?;
+
+    let stdout = process
+        .stdout
+        .take()
+        .expect("driver was spawned with a piped stdout");
+    let stderr = process
+        .stderr
+        .take()
+        .expect("driver was spawned with a piped stderr");
+    wait_until_ready(browser, stdout, stderr, port).await?;
+
     Ok(Driver { url, process })
 }
+
+/// Whether an already-cached driver binary should be reused as-is, rather
+/// than patched/copied from an explicit path or freshly downloaded. An
+/// explicit driver path (from `with_chromedriver_path`/`use_path_driver`)
+/// always wins, even when the cache already holds a driver for the same
+/// `requested_version_key` — otherwise a stale or pre-seeded cache entry
+/// (e.g. baked into a CI image) would silently shadow the caller's choice.
+fn should_use_cached_driver(
+    existing_driver_path_given: bool,
+    cached_version_key: Option<&str>,
+    requested_version_key: &str,
+    driver_file_exists: bool,
+) -> bool {
+    !existing_driver_path_given
+        && cached_version_key.is_some_and(|key| key == requested_version_key)
+        && driver_file_exists
+}
+
+/// Scans `src` for chromedriver's `cdc_` automation markers and writes a
+/// copy with the bytes following each marker randomized to `dest`, so sites
+/// checking `window.cdc_...` globals can't detect the driver.
+async fn patch_chromedriver(src: &Path, dest: &Path) -> anyhow::Result<()> {
+    log::info!("patching chromedriver executable");
+    let bytes = tokio::fs::read(src).await?;
+    let mut patched = bytes.clone();
+    let mut cdc_pos_list = Vec::new();
+    for i in 0..bytes.len() - 3 {
+        if &bytes[i..i + 4] == b"cdc_" {
+            cdc_pos_list.push(i);
+        }
+    }
+    if cdc_pos_list.is_empty() {
+        log::info!("No cdcs were found!");
+    } else {
+        log::info!("Found cdcs!");
+    }
+
+    let patch_ct = cdc_pos_list.len();
+    for i in cdc_pos_list {
+        for x in i + 4..i + 22 {
+            patched[x] = random_char();
+        }
+    }
+    log::info!("Patched {} cdcs!", patch_ct);
+    log::info!("Writing patched executable to {}...", dest.display());
+    tokio::fs::write(dest, patched).await?;
+    log::info!("Successfully wrote patched executable to {}", dest.display());
+    Ok(())
+}
+
+/// Binds a `TcpListener` on port 0 to obtain a port the OS guarantees is
+/// currently free, then releases it so chromedriver can bind it in turn.
+/// Port 0 always resolves to a free port or fails outright, so there's no
+/// `AddrInUse` case to retry around here.
+fn find_available_port() -> Result<u16, DriverError> {
+    let listener =
+        TcpListener::bind(("127.0.0.1", 0)).map_err(|_| DriverError::NoAvailablePorts)?;
+    let port = listener
+        .local_addr()
+        .map_err(|_| DriverError::NoAvailablePorts)?
+        .port();
+    drop(listener);
+    Ok(port)
+}
+
+/// Reads the driver's stdout and stderr until either reports it is ready to
+/// accept connections on `port`, or bails out with
+/// [`DriverError::PortOpenTimeout`]. Both streams are read because which one
+/// carries the ready message is driver-specific (geckodriver uses stderr;
+/// chromedriver and msedgedriver use stdout).
+async fn wait_until_ready(
+    browser: Browser,
+    stdout: tokio::process::ChildStdout,
+    stderr: tokio::process::ChildStderr,
+    port: u16,
+) -> Result<(), DriverError> {
+    let ready_message = browser.ready_message(port);
+    tokio::time::timeout(
+        Duration::from_secs(20),
+        any_stream_contains(stdout, stderr, &ready_message),
+    )
+    .await
+    .ok()
+    .filter(|&found| found)
+    .map(|_| ())
+    .ok_or(DriverError::PortOpenTimeout)
+}
+
+/// Races two line streams, returning `true` as soon as either yields a line
+/// containing `message`, or `false` once both have hit EOF/an error without
+/// a match.
+async fn any_stream_contains<R1, R2>(stream_a: R1, stream_b: R2, message: &str) -> bool
+where
+    R1: tokio::io::AsyncRead + Unpin,
+    R2: tokio::io::AsyncRead + Unpin,
+{
+    let mut lines_a = BufReader::new(stream_a).lines();
+    let mut lines_b = BufReader::new(stream_b).lines();
+    let mut a_done = false;
+    let mut b_done = false;
+
+    loop {
+        if a_done && b_done {
+            return false;
+        }
+        tokio::select! {
+            line = lines_a.next_line(), if !a_done => {
+                match line {
+                    Ok(Some(line)) if line.contains(message) => return true,
+                    Ok(Some(_)) => {}
+                    _ => a_done = true,
+                }
+            }
+            line = lines_b.next_line(), if !b_done => {
+                match line {
+                    Ok(Some(line)) if line.contains(message) => return true,
+                    Ok(Some(_)) => {}
+                    _ => b_done = true,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_available_port_returns_a_bindable_port() {
+        let port = find_available_port().unwrap();
+        assert!(TcpListener::bind(("127.0.0.1", port)).is_ok());
+    }
+
+    #[test]
+    fn driver_file_name_matches_os_and_browser() {
+        assert_eq!(Browser::Chrome.driver_file_name(OS::Linux), "chromedriver");
+        assert_eq!(
+            Browser::Chrome.driver_file_name(OS::Windows),
+            "chromedriver.exe"
+        );
+        assert_eq!(Browser::Firefox.driver_file_name(OS::MacOS), "geckodriver");
+        assert_eq!(
+            Browser::Firefox.driver_file_name(OS::Windows),
+            "geckodriver.exe"
+        );
+        assert_eq!(Browser::Edge.driver_file_name(OS::Linux), "msedgedriver");
+        assert_eq!(
+            Browser::Edge.driver_file_name(OS::Windows),
+            "msedgedriver.exe"
+        );
+    }
+
+    #[test]
+    fn only_chrome_needs_the_cdc_patch() {
+        assert!(Browser::Chrome.needs_cdc_patch());
+        assert!(!Browser::Firefox.needs_cdc_patch());
+        assert!(!Browser::Edge.needs_cdc_patch());
+    }
+
+    /// These strings must match each driver's actual startup banner, or
+    /// `wait_until_ready` times out on every launch with a generic
+    /// `PortOpenTimeout` instead of a useful error.
+    #[test]
+    fn ready_message_matches_each_driver_s_startup_banner() {
+        assert_eq!(
+            Browser::Chrome.ready_message(9515),
+            "ChromeDriver was started successfully on port 9515"
+        );
+        assert_eq!(
+            Browser::Edge.ready_message(9515),
+            "Microsoft Edge WebDriver was started successfully on port 9515"
+        );
+        assert_eq!(
+            Browser::Firefox.ready_message(9515),
+            "Listening on 127.0.0.1:9515"
+        );
+    }
+
+    /// A stale or pre-seeded cache entry must never shadow an explicit
+    /// `with_chromedriver_path`/`use_path_driver()` request, even when the
+    /// cached version key matches and the driver file is already present.
+    #[test]
+    fn explicit_driver_path_is_not_shadowed_by_a_fresh_cache() {
+        assert!(!should_use_cached_driver(true, Some("122"), "122", true));
+    }
+
+    #[test]
+    fn fresh_cache_is_used_when_no_explicit_driver_path_was_given() {
+        assert!(should_use_cached_driver(false, Some("122"), "122", true));
+    }
+
+    #[test]
+    fn stale_cache_is_not_used_even_without_an_explicit_driver_path() {
+        assert!(!should_use_cached_driver(false, Some("121"), "122", true));
+        assert!(!should_use_cached_driver(false, None, "122", true));
+        assert!(!should_use_cached_driver(false, Some("122"), "122", false));
+    }
+
+    #[tokio::test]
+    async fn any_stream_contains_finds_the_message_on_stdout() {
+        let stdout = b"starting up\nChromeDriver was started successfully on port 9515\n".as_slice();
+        let stderr = b"".as_slice();
+        assert!(
+            any_stream_contains(
+                stdout,
+                stderr,
+                "ChromeDriver was started successfully on port 9515"
+            )
+            .await
+        );
+    }
+
+    /// geckodriver writes its ready banner to stderr, not stdout; this is the
+    /// case that broke `firefox()` before both streams were read.
+    #[tokio::test]
+    async fn any_stream_contains_finds_the_message_on_stderr() {
+        let stdout = b"".as_slice();
+        let stderr = b"1970-01-01 Listening on 127.0.0.1:9515\n".as_slice();
+        assert!(any_stream_contains(stdout, stderr, "Listening on 127.0.0.1:9515").await);
+    }
+
+    #[tokio::test]
+    async fn any_stream_contains_returns_false_on_eof_without_a_match() {
+        let stdout = b"starting up\nstill starting\n".as_slice();
+        let stderr = b"also starting\n".as_slice();
+        assert!(!any_stream_contains(stdout, stderr, "ready").await);
+    }
+}